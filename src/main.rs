@@ -1,7 +1,8 @@
 use rand::Rng;
+use rayon::prelude::*;
 use std::io;
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 enum Player {
     X,
     O,
@@ -21,52 +22,159 @@ impl Player {
             Player::O => 'O',
         }
     }
+
+    fn from_char(c: char) -> Player {
+        match c {
+            'X' => Player::X,
+            'O' => Player::O,
+            _ => panic!("not a player mark: {:?}", c),
+        }
+    }
+}
+
+// A two-player game that minimax can search; `apply` plays the mark for
+// whichever player is currently to move.
+trait Game: Clone {
+    type Move: Copy;
+
+    fn moves(&self) -> Vec<Self::Move>;
+    fn apply(&self, m: Self::Move) -> Self;
+    fn winner(&self) -> Option<Player>;
+    fn is_full(&self) -> bool;
+}
+
+#[derive(Clone)]
+struct Board {
+    cells: Vec<char>,
+    n: usize,
+    k: usize,
+    to_move: Player,
+}
+
+impl Board {
+    fn new(n: usize, k: usize) -> Self {
+        Board {
+            cells: vec![' '; n * n],
+            n,
+            k,
+            to_move: Player::X,
+        }
+    }
+}
+
+impl Game for Board {
+    type Move = usize;
+
+    fn moves(&self) -> Vec<usize> {
+        (0..self.cells.len())
+            .filter(|&i| self.cells[i] == ' ')
+            .collect()
+    }
+
+    fn apply(&self, m: usize) -> Board {
+        let mut next = self.clone();
+        next.cells[m] = self.to_move.as_char();
+        next.to_move = self.to_move.toggle();
+        next
+    }
+
+    fn winner(&self) -> Option<Player> {
+        check_winner(&self.cells, self.n, self.k)
+    }
+
+    fn is_full(&self) -> bool {
+        is_board_full(&self.cells)
+    }
 }
 
 fn main() {
-    let mut board = [' '; 9];
+    let (n, k) = ask_board_size();
+    let depth_cap = ask_difficulty();
+    let mut board = Board::new(n, k);
     let mut rng = rand::thread_rng();
     let human_player = if rng.gen() { Player::X } else { Player::O };
     let computer_player = human_player.toggle();
 
     println!("You are player {}", human_player.as_char());
 
-    // Set the current player to X
-    let mut current_player = Player::X;
-
     // If the human player is O, computer makes the first move
     if human_player == Player::O {
-        computer_turn_minimax(&mut board, computer_player, human_player);
-        current_player = current_player.toggle(); // Toggle the player so that human is next
+        computer_turn_minimax(&mut board, computer_player, human_player, depth_cap);
     }
 
-    print_board(&board);
+    print_board(&board.cells, n);
 
     loop {
-        if current_player == human_player {
-            let choice = get_player_choice(&mut board);
-            board[choice] = current_player.as_char();
+        if board.to_move == human_player {
+            let choice = get_player_choice(&board.cells, n);
+            board = board.apply(choice);
         } else {
-            computer_turn_minimax(&mut board, computer_player, human_player);
+            computer_turn_minimax(&mut board, computer_player, human_player, depth_cap);
         }
 
-        print_board(&board);
+        print_board(&board.cells, n);
 
-        if let Some(winner) = check_winner(&board) {
+        if let Some(winner) = board.winner() {
             println!("Player {} wins!", winner.as_char());
             break;
-        } else if board.iter().all(|&x| x != ' ') {
+        } else if board.is_full() {
             println!("It's a tie!");
             break;
         }
+    }
+}
+
+fn ask_board_size() -> (usize, usize) {
+    let n = loop {
+        println!("Enter board size (N for an N x N board): ");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        match input.trim().parse::<usize>() {
+            Ok(n) if n >= 3 => break n,
+            _ => println!("Invalid input, please try again."),
+        }
+    };
+
+    let k = loop {
+        println!("Enter how many in a row are needed to win: ");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        match input.trim().parse::<usize>() {
+            Ok(k) if k >= 1 && k <= n => break k,
+            _ => println!("Invalid input, please try again."),
+        }
+    };
 
-        current_player = current_player.toggle();
+    (n, k)
+}
+
+// Maps a difficulty choice to the max search depth passed down to minimax.
+fn ask_difficulty() -> i32 {
+    loop {
+        println!("Select difficulty: 1) Easy  2) Medium  3) Hard");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        match input.trim() {
+            "1" => break 1,
+            "2" => break 4,
+            "3" => break i32::MAX,
+            _ => println!("Invalid input, please try again."),
+        }
     }
 }
 
-fn print_board(board: &[char; 9]) {
+fn print_board(board: &[char], n: usize) {
     for (i, &cell) in board.iter().enumerate() {
-        if i % 3 == 0 {
+        if i % n == 0 {
             println!();
         }
         if cell == ' ' {
@@ -78,129 +186,347 @@ fn print_board(board: &[char; 9]) {
     println!("\n");
 }
 
-fn get_player_choice(board: &mut [char; 9]) -> usize {
+fn get_player_choice(board: &[char], n: usize) -> usize {
     loop {
-        println!("Enter your move (0-8): ");
+        println!("Enter your move (0-{}, or e.g. b3): ", board.len() - 1);
         let mut input = String::new();
         io::stdin()
             .read_line(&mut input)
             .expect("Failed to read line");
 
-        match input.trim().parse::<usize>() {
-            Ok(index) if index < 9 && board[index] == ' ' => return index,
+        match parse_move(input.trim(), n) {
+            Some(index) if index < n * n && board[index] == ' ' => return index,
             _ => println!("Invalid input, please try again."),
         }
     }
 }
 
-fn check_winner(board: &[char; 9]) -> Option<Player> {
-    let check_line = |player: char, a: usize, b: usize, c: usize| {
-        board[a] == player && board[b] == player && board[c] == player
-    };
-
-    for &player in &[Player::X, Player::O] {
-        let player_char = player.as_char();
+// Accepts a plain flat index ("4") or an algebraic coordinate ("b3").
+fn parse_move(input: &str, n: usize) -> Option<usize> {
+    if let Ok(index) = input.parse::<usize>() {
+        return Some(index);
+    }
 
-        // Check horizontal lines
-        for row in 0..3 {
-            if check_line(player_char, row * 3, row * 3 + 1, row * 3 + 2) {
-                return Some(player);
+    let bytes = input.as_bytes();
+    if bytes.len() == 2 {
+        let column_byte = bytes[0].to_ascii_lowercase();
+        let row_byte = bytes[1];
+        if column_byte.is_ascii_lowercase() && (b'1'..=b'9').contains(&row_byte) {
+            let column = (column_byte - b'a') as usize;
+            let row = (row_byte - b'1') as usize;
+            if column < n && row < n {
+                return Some(row * n + column);
             }
         }
+    }
 
-        // Check vertical lines
-        for col in 0..3 {
-            if check_line(player_char, col, col + 3, col + 6) {
-                return Some(player);
+    None
+}
+
+fn check_winner(board: &[char], n: usize, k: usize) -> Option<Player> {
+    let directions: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+    for row in 0..n {
+        for col in 0..n {
+            let mark = board[row * n + col];
+            if mark == ' ' {
+                continue;
             }
-        }
 
-        // Check diagonals
-        if check_line(player_char, 0, 4, 8) || check_line(player_char, 2, 4, 6) {
-            return Some(player);
+            for &(dr, dc) in &directions {
+                let end_row = row as isize + dr * (k as isize - 1);
+                let end_col = col as isize + dc * (k as isize - 1);
+                if end_row < 0 || end_row >= n as isize || end_col < 0 || end_col >= n as isize {
+                    continue;
+                }
+
+                let runs = (0..k as isize).all(|step| {
+                    let r = (row as isize + dr * step) as usize;
+                    let c = (col as isize + dc * step) as usize;
+                    board[r * n + c] == mark
+                });
+
+                if runs {
+                    return Some(Player::from_char(mark));
+                }
+            }
         }
     }
 
     None
 }
 
-fn evaluate_board(board: &[char; 9], computer_player: Player, human_player: Player) -> i32 {
-    match check_winner(board) {
+fn evaluate_game<G: Game>(game: &G, computer_player: Player, human_player: Player) -> i32 {
+    match game.winner() {
         Some(player) if player == computer_player => 1, // Computer wins
         Some(player) if player == human_player => -1,   // Human wins
         Some(_) | None => 0,                            // Draw or undecided
     }
 }
 
-fn minimax(
-    board: &mut [char; 9],
+// Prefers the fastest win and the slowest loss.
+fn weighted_score(raw: i32, depth: i32) -> i32 {
+    match raw {
+        1 => 10 + depth,
+        -1 => -10 - depth,
+        _ => 0,
+    }
+}
+
+fn minimax<G: Game>(
+    game: &G,
     depth: i32,
     is_maximizing: bool,
+    mut alpha: i32,
+    mut beta: i32,
     computer_player: Player,
     human_player: Player,
 ) -> i32 {
-    let score = evaluate_board(board, computer_player, human_player);
+    let raw = evaluate_game(game, computer_player, human_player);
 
-    if score != 0 || depth == 0 || is_board_full(board) {
-        return score;
+    if raw != 0 || depth == 0 || game.is_full() {
+        return weighted_score(raw, depth);
     }
 
     if is_maximizing {
         let mut best_score = i32::MIN;
-        for i in 0..board.len() {
-            if board[i] == ' ' {
-                board[i] = computer_player.as_char();
-                best_score = best_score.max(minimax(
-                    board,
-                    depth - 1,
-                    false,
-                    computer_player,
-                    human_player,
-                ));
-                board[i] = ' ';
+        for m in game.moves() {
+            let child = game.apply(m);
+            best_score = best_score.max(minimax(
+                &child,
+                depth - 1,
+                false,
+                alpha,
+                beta,
+                computer_player,
+                human_player,
+            ));
+            alpha = alpha.max(best_score);
+            if beta <= alpha {
+                break;
             }
         }
         best_score
     } else {
         let mut best_score = i32::MAX;
-        for i in 0..board.len() {
-            if board[i] == ' ' {
-                board[i] = human_player.as_char();
-                best_score = best_score.min(minimax(
-                    board,
-                    depth - 1,
-                    true,
-                    computer_player,
-                    human_player,
-                ));
-                board[i] = ' ';
+        for m in game.moves() {
+            let child = game.apply(m);
+            best_score = best_score.min(minimax(
+                &child,
+                depth - 1,
+                true,
+                alpha,
+                beta,
+                computer_player,
+                human_player,
+            ));
+            beta = beta.min(best_score);
+            if beta <= alpha {
+                break;
             }
         }
         best_score
     }
 }
 
-fn is_board_full(board: &[char; 9]) -> bool {
+fn is_board_full(board: &[char]) -> bool {
     board.iter().all(|&cell| cell != ' ')
 }
 
-fn computer_turn_minimax(board: &mut [char; 9], computer_player: Player, human_player: Player) {
-    let mut best_score = i32::MIN;
-    let mut best_move = None;
+fn computer_turn_minimax<G: Game + Sync>(
+    game: &mut G,
+    computer_player: Player,
+    human_player: Player,
+    depth_cap: i32,
+) where
+    G::Move: Send,
+{
+    let max_depth = (game.moves().len() as i32).min(depth_cap);
+    let game_ref: &G = game;
+
+    // Each root move spawns an independent subtree on its own cloned board,
+    // so the root search fans out across threads instead of looping serially.
+    let best_move = game_ref
+        .moves()
+        .into_par_iter()
+        .map(|m| {
+            let child = game_ref.apply(m);
+            let score = minimax(
+                &child,
+                max_depth - 1,
+                false,
+                i32::MIN,
+                i32::MAX,
+                computer_player,
+                human_player,
+            );
+            (m, score)
+        })
+        .reduce_with(|a, b| if b.1 > a.1 { b } else { a });
+
+    if let Some((m, _)) = best_move {
+        *game = game.apply(m);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_winner_finds_row() {
+        let mut board = vec![' '; 9];
+        board[3] = 'X';
+        board[4] = 'X';
+        board[5] = 'X';
+        assert_eq!(check_winner(&board, 3, 3), Some(Player::X));
+    }
+
+    #[test]
+    fn check_winner_finds_diagonal() {
+        let mut board = vec![' '; 9];
+        board[0] = 'O';
+        board[4] = 'O';
+        board[8] = 'O';
+        assert_eq!(check_winner(&board, 3, 3), Some(Player::O));
+    }
+
+    #[test]
+    fn check_winner_none_without_k_in_a_row() {
+        let mut board = vec![' '; 9];
+        board[0] = 'X';
+        board[1] = 'X';
+        assert_eq!(check_winner(&board, 3, 3), None);
+    }
+
+    #[test]
+    fn parse_move_accepts_numeric_index() {
+        assert_eq!(parse_move("4", 3), Some(4));
+    }
+
+    #[test]
+    fn parse_move_accepts_algebraic_coordinate() {
+        assert_eq!(parse_move("b3", 3), Some(2 * 3 + 1));
+    }
+
+    #[test]
+    fn parse_move_rejects_row_zero_instead_of_underflowing() {
+        assert_eq!(parse_move("b0", 3), None);
+    }
 
-    for i in 0..board.len() {
-        if board[i] == ' ' {
-            board[i] = computer_player.as_char();
-            let score = minimax(board, 9, false, computer_player, human_player);
-            board[i] = ' ';
-            if score > best_score {
-                best_score = score;
-                best_move = Some(i);
+    #[test]
+    fn parse_move_rejects_garbage() {
+        assert_eq!(parse_move("!!", 3), None);
+        assert_eq!(parse_move("", 3), None);
+    }
+
+    #[test]
+    fn parse_move_rejects_out_of_range_column_or_row() {
+        assert_eq!(parse_move("d1", 3), None);
+        assert_eq!(parse_move("a4", 3), None);
+    }
+
+    #[test]
+    fn minimax_prunes_to_the_same_score_as_an_unbounded_window() {
+        // X to move, and X can complete the top row for an immediate win.
+        let mut board = Board::new(3, 3);
+        board.cells = vec!['X', 'X', ' ', 'O', 'O', ' ', ' ', ' ', ' '];
+        board.to_move = Player::X;
+
+        // A beta of 1 makes the winning first move (cell 2) cut off the rest.
+        let pruned = minimax(&board, 1, true, i32::MIN, 1, Player::X, Player::O);
+        let unbounded = minimax(&board, 1, true, i32::MIN, i32::MAX, Player::X, Player::O);
+
+        assert_eq!(pruned, 10);
+        assert_eq!(pruned, unbounded);
+    }
+
+    #[test]
+    fn weighted_score_prefers_the_faster_win_and_the_slower_loss() {
+        assert!(weighted_score(1, 3) > weighted_score(1, 1));
+        assert!(weighted_score(-1, 1) > weighted_score(-1, 3));
+    }
+
+    // A tiny take-1-or-2 Nim pile, just to prove minimax works against a
+    // Game impl that isn't Board. Whoever takes the last stone wins.
+    #[derive(Clone)]
+    struct NimPile {
+        remaining: u32,
+        to_move: Player,
+    }
+
+    impl Game for NimPile {
+        type Move = u32;
+
+        fn moves(&self) -> Vec<u32> {
+            (1..=self.remaining.min(2)).collect()
+        }
+
+        fn apply(&self, m: u32) -> NimPile {
+            NimPile {
+                remaining: self.remaining - m,
+                to_move: self.to_move.toggle(),
             }
         }
+
+        fn winner(&self) -> Option<Player> {
+            if self.remaining == 0 {
+                Some(self.to_move.toggle())
+            } else {
+                None
+            }
+        }
+
+        fn is_full(&self) -> bool {
+            self.remaining == 0
+        }
+    }
+
+    #[test]
+    fn minimax_is_generic_over_games_other_than_board() {
+        // 3 stones is a losing position for whoever moves next under optimal play.
+        let losing = NimPile {
+            remaining: 3,
+            to_move: Player::X,
+        };
+        let score = minimax(&losing, 10, true, i32::MIN, i32::MAX, Player::X, Player::O);
+        assert!(score < 0);
+
+        // 4 stones is a winning position for whoever moves next under optimal play.
+        let winning = NimPile {
+            remaining: 4,
+            to_move: Player::X,
+        };
+        let score = minimax(&winning, 10, true, i32::MIN, i32::MAX, Player::X, Player::O);
+        assert!(score > 0);
     }
 
-    if let Some(move_index) = best_move {
-        board[move_index] = computer_player.as_char();
+    #[test]
+    fn depth_cap_can_hide_a_forced_loss_that_full_depth_finds() {
+        // 9 stones is a losing position, but it takes several plies to prove,
+        // so a shallow difficulty-level depth cap can't see it yet.
+        let pile = NimPile {
+            remaining: 9,
+            to_move: Player::X,
+        };
+
+        let shallow = minimax(&pile, 1, true, i32::MIN, i32::MAX, Player::X, Player::O);
+        assert_eq!(shallow, 0);
+
+        let deep = minimax(&pile, 9, true, i32::MIN, i32::MAX, Player::X, Player::O);
+        assert!(deep < 0);
+    }
+
+    #[test]
+    fn computer_turn_minimax_picks_the_winning_root_move() {
+        // X to move, and completing the top row is the only winning move.
+        let mut board = Board::new(3, 3);
+        board.cells = vec!['X', 'X', ' ', 'O', 'O', ' ', ' ', ' ', ' '];
+        board.to_move = Player::X;
+
+        computer_turn_minimax(&mut board, Player::X, Player::O, i32::MAX);
+
+        assert_eq!(board.cells[2], 'X');
+        assert_eq!(board.winner(), Some(Player::X));
     }
 }